@@ -1,7 +1,7 @@
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::character::complete::char as cchar;
-use nom::combinator::{map, opt};
+use nom::combinator::{consumed, map, opt};
 use nom::multi::separated_list0;
 use nom::sequence::{delimited, pair, preceded, terminated, tuple};
 use nom::IResult;
@@ -15,19 +15,55 @@ use crate::functions::{Function, FunctionRef};
 use crate::types::{FieldType, FieldTypeRef};
 use crate::Parser;
 
+/// A byte-offset range into the original source, marking where a parsed
+/// node (including its leading doc-comment, if any) was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Alias used for sub-nodes (enum values) to keep call sites
+/// self-documenting without implying a different representation than [`Span`].
+/// Fields and functions carry no `Location`.
+pub type Location = Span;
+
+/// Computes the [`Span`] of `consumed` relative to `origin`, assuming `consumed`
+/// is a sub-slice of `origin` (true for any `&str` produced by parsing `origin`
+/// or one of its sub-slices). Pass the *whole* document as `origin`, not just
+/// the slice a given parser was called with, or nested/later spans come out
+/// relative to that slice instead of absolute offsets into the document.
+fn span_of(origin: &str, consumed: &str) -> Span {
+    let start = consumed.as_ptr() as usize - origin.as_ptr() as usize;
+    Span {
+        start,
+        end: start + consumed.len(),
+    }
+}
+
+// `FieldRef` (`crate::field`) and `FunctionRef` (`crate::functions`) are
+// defined outside this module and still parse via the plain `Parser::parse(&'a
+// str) -> IResult<&'a str, Self>` trait method, so they have no `origin` to
+// thread through and carry no `span`/`location` yet. `Struct`/`Union`/
+// `Exception`/`Service` bodies will keep exposing unspanned fields and
+// functions until those modules adopt the same `parse_in` pattern used below.
+
 // Const           ::=  'const' FieldType Identifier '=' ConstValue ListSeparator?
 #[derive(Debug, Clone, PartialEq)]
 pub struct ConstRef<'a> {
+    pub span: Span,
     pub doc_comment: Option<CommentRef<'a>>,
     pub name: IdentifierRef<'a>,
     pub type_: FieldTypeRef<'a>,
     pub value: ConstValueRef<'a>,
 }
 
-impl<'a> Parser<'a> for ConstRef<'a> {
-    fn parse(input: &'a str) -> IResult<&'a str, Self> {
+impl<'a> ConstRef<'a> {
+    /// Parses starting at `input`, computing `span` as an absolute offset
+    /// into `origin` (the whole document `input` is a suffix of).
+    pub(crate) fn parse_in(input: &'a str, origin: &'a str) -> IResult<&'a str, Self> {
         map(
-            tuple((
+            consumed(tuple((
                 opt(terminated(
                     CommentRef::parse,
                     terminated(Linefeed::parse, opt(Space::parse)),
@@ -38,8 +74,9 @@ impl<'a> Parser<'a> for ConstRef<'a> {
                 preceded(opt(Separator::parse), cchar('=')),
                 preceded(opt(Separator::parse), ConstValueRef::parse),
                 opt(pair(opt(Separator::parse), ListSeparator::parse)),
-            )),
-            |(doc_comment, _, type_, name, _, value, _)| Self {
+            ))),
+            |(consumed, (doc_comment, _, type_, name, _, value, _))| Self {
+                span: span_of(origin, consumed),
                 doc_comment,
                 name,
                 type_,
@@ -49,8 +86,15 @@ impl<'a> Parser<'a> for ConstRef<'a> {
     }
 }
 
+impl<'a> Parser<'a> for ConstRef<'a> {
+    fn parse(input: &'a str) -> IResult<&'a str, Self> {
+        Self::parse_in(input, input)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Const {
+    pub span: Span,
     pub doc_comment: Option<Comment>,
     pub name: Identifier,
     pub type_: FieldType,
@@ -60,6 +104,7 @@ pub struct Const {
 impl<'a> From<ConstRef<'a>> for Const {
     fn from(r: ConstRef<'a>) -> Self {
         Self {
+            span: r.span,
             doc_comment: match r.doc_comment {
                 Some(d) => Some(d.into()),
                 None => None,
@@ -83,15 +128,18 @@ impl<'a> Parser<'a> for Const {
 // ContainerType   ::=  MapType | SetType | ListType
 #[derive(Debug, Clone, PartialEq)]
 pub struct TypedefRef<'a> {
+    pub span: Span,
     pub doc_comment: Option<CommentRef<'a>>,
     pub old: FieldTypeRef<'a>,
     pub alias: IdentifierRef<'a>,
 }
 
-impl<'a> Parser<'a> for TypedefRef<'a> {
-    fn parse(input: &'a str) -> IResult<&'a str, Self> {
+impl<'a> TypedefRef<'a> {
+    /// Parses starting at `input`, computing `span` as an absolute offset
+    /// into `origin` (the whole document `input` is a suffix of).
+    pub(crate) fn parse_in(input: &'a str, origin: &'a str) -> IResult<&'a str, Self> {
         map(
-            tuple((
+            consumed(tuple((
                 opt(terminated(
                     CommentRef::parse,
                     terminated(Linefeed::parse, opt(Space::parse)),
@@ -105,8 +153,9 @@ impl<'a> Parser<'a> for TypedefRef<'a> {
                     )),
                 ),
                 preceded(Separator::parse, IdentifierRef::parse),
-            )),
-            |(doc_comment, _, old, alias)| TypedefRef {
+            ))),
+            |(consumed, (doc_comment, _, old, alias))| TypedefRef {
+                span: span_of(origin, consumed),
                 doc_comment,
                 old,
                 alias,
@@ -115,8 +164,15 @@ impl<'a> Parser<'a> for TypedefRef<'a> {
     }
 }
 
+impl<'a> Parser<'a> for TypedefRef<'a> {
+    fn parse(input: &'a str) -> IResult<&'a str, Self> {
+        Self::parse_in(input, input)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Typedef {
+    pub span: Span,
     pub doc_comment: Option<Comment>,
     pub old: FieldType,
     pub alias: Identifier,
@@ -125,6 +181,7 @@ pub struct Typedef {
 impl<'a> From<TypedefRef<'a>> for Typedef {
     fn from(r: TypedefRef<'a>) -> Self {
         Self {
+            span: r.span,
             doc_comment: match r.doc_comment {
                 Some(d) => Some(d.into()),
                 None => None,
@@ -144,6 +201,7 @@ impl<'a> Parser<'a> for Typedef {
 // Enum            ::=  'enum' Identifier '{' (Identifier ('=' IntConstant)? ListSeparator?)* '}'
 #[derive(Debug, Clone, PartialEq)]
 pub struct EnumRef<'a> {
+    pub span: Span,
     pub doc_comment: Option<CommentRef<'a>>,
     pub name: IdentifierRef<'a>,
     pub children: Vec<EnumValueRef<'a>>,
@@ -151,14 +209,18 @@ pub struct EnumRef<'a> {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct EnumValueRef<'a> {
+    pub location: Location,
     pub name: IdentifierRef<'a>,
     pub value: Option<IntConstant>,
 }
 
-impl<'a> Parser<'a> for EnumRef<'a> {
-    fn parse(input: &'a str) -> IResult<&'a str, Self> {
+impl<'a> EnumRef<'a> {
+    /// Parses starting at `input`, computing `span`/children's `location` as
+    /// absolute offsets into `origin` (the whole document `input` is a suffix
+    /// of).
+    pub(crate) fn parse_in(input: &'a str, origin: &'a str) -> IResult<&'a str, Self> {
         map(
-            tuple((
+            consumed(tuple((
                 opt(terminated(
                     CommentRef::parse,
                     terminated(Linefeed::parse, opt(Space::parse)),
@@ -166,11 +228,12 @@ impl<'a> Parser<'a> for EnumRef<'a> {
                 tag("enum"),
                 preceded(Separator::parse, IdentifierRef::parse),
                 tuple((opt(Separator::parse), cchar('{'), opt(Separator::parse))),
-                separated_list0(parse_list_separator, EnumValueRef::parse),
+                separated_list0(parse_list_separator, |i| EnumValueRef::parse_in(i, origin)),
                 opt(parse_list_separator),
                 preceded(opt(Separator::parse), cchar('}')),
-            )),
-            |(doc_comment, _, name, _, children, _, _)| Self {
+            ))),
+            |(consumed, (doc_comment, _, name, _, children, _, _))| Self {
+                span: span_of(origin, consumed),
                 doc_comment,
                 name,
                 children,
@@ -179,10 +242,18 @@ impl<'a> Parser<'a> for EnumRef<'a> {
     }
 }
 
-impl<'a> Parser<'a> for EnumValueRef<'a> {
+impl<'a> Parser<'a> for EnumRef<'a> {
     fn parse(input: &'a str) -> IResult<&'a str, Self> {
+        Self::parse_in(input, input)
+    }
+}
+
+impl<'a> EnumValueRef<'a> {
+    /// Parses starting at `input`, computing `location` as an absolute
+    /// offset into `origin` (the whole document `input` is a suffix of).
+    pub(crate) fn parse_in(input: &'a str, origin: &'a str) -> IResult<&'a str, Self> {
         map(
-            tuple((
+            consumed(tuple((
                 IdentifierRef::parse,
                 opt(map(
                     tuple((
@@ -193,14 +264,25 @@ impl<'a> Parser<'a> for EnumValueRef<'a> {
                     )),
                     |(_, _, _, i)| (i),
                 )),
-            )),
-            |(name, value)| Self { name, value },
+            ))),
+            |(consumed, (name, value))| Self {
+                location: span_of(origin, consumed),
+                name,
+                value,
+            },
         )(input)
     }
 }
 
+impl<'a> Parser<'a> for EnumValueRef<'a> {
+    fn parse(input: &'a str) -> IResult<&'a str, Self> {
+        Self::parse_in(input, input)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Enum {
+    pub span: Span,
     pub doc_comment: Option<Comment>,
     pub name: Identifier,
     pub children: Vec<EnumValue>,
@@ -208,6 +290,7 @@ pub struct Enum {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct EnumValue {
+    pub location: Location,
     pub name: Identifier,
     pub value: Option<IntConstant>,
 }
@@ -215,6 +298,7 @@ pub struct EnumValue {
 impl<'a> From<EnumRef<'a>> for Enum {
     fn from(r: EnumRef<'a>) -> Self {
         Self {
+            span: r.span,
             doc_comment: match r.doc_comment {
                 Some(e) => Some(e.into()),
                 None => None,
@@ -228,6 +312,7 @@ impl<'a> From<EnumRef<'a>> for Enum {
 impl<'a> From<EnumValueRef<'a>> for EnumValue {
     fn from(r: EnumValueRef<'a>) -> Self {
         Self {
+            location: r.location,
             name: r.name.into(),
             value: r.value,
         }
@@ -249,15 +334,18 @@ impl<'a> Parser<'a> for EnumValue {
 // Struct          ::=  'struct' Identifier '{' Field* '}'
 #[derive(Debug, Clone, PartialEq)]
 pub struct StructRef<'a> {
+    pub span: Span,
     pub doc_comment: Option<CommentRef<'a>>,
     pub name: IdentifierRef<'a>,
     pub fields: Vec<FieldRef<'a>>,
 }
 
-impl<'a> Parser<'a> for StructRef<'a> {
-    fn parse(input: &'a str) -> IResult<&'a str, Self> {
+impl<'a> StructRef<'a> {
+    /// Parses starting at `input`, computing `span` as an absolute offset
+    /// into `origin` (the whole document `input` is a suffix of).
+    pub(crate) fn parse_in(input: &'a str, origin: &'a str) -> IResult<&'a str, Self> {
         map(
-            tuple((
+            consumed(tuple((
                 opt(terminated(
                     CommentRef::parse,
                     terminated(Linefeed::parse, opt(Space::parse)),
@@ -267,8 +355,9 @@ impl<'a> Parser<'a> for StructRef<'a> {
                 delimited(opt(Separator::parse), cchar('{'), opt(Separator::parse)),
                 separated_list0(Separator::parse, FieldRef::parse),
                 pair(opt(Separator::parse), cchar('}')),
-            )),
-            |(doc_comment, _, name, _, fields, _)| Self {
+            ))),
+            |(consumed, (doc_comment, _, name, _, fields, _))| Self {
+                span: span_of(origin, consumed),
                 doc_comment,
                 name,
                 fields,
@@ -277,8 +366,15 @@ impl<'a> Parser<'a> for StructRef<'a> {
     }
 }
 
+impl<'a> Parser<'a> for StructRef<'a> {
+    fn parse(input: &'a str) -> IResult<&'a str, Self> {
+        Self::parse_in(input, input)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Struct {
+    pub span: Span,
     pub doc_comment: Option<Comment>,
     pub name: Identifier,
     pub fields: Vec<Field>,
@@ -287,6 +383,7 @@ pub struct Struct {
 impl<'a> From<StructRef<'a>> for Struct {
     fn from(r: StructRef<'a>) -> Self {
         Self {
+            span: r.span,
             doc_comment: match r.doc_comment {
                 Some(d) => Some(d.into()),
                 None => None,
@@ -306,15 +403,18 @@ impl<'a> Parser<'a> for Struct {
 // Union          ::=  'union' Identifier '{' Field* '}'
 #[derive(Debug, Clone, PartialEq)]
 pub struct UnionRef<'a> {
+    pub span: Span,
     pub doc_comment: Option<CommentRef<'a>>,
     pub name: IdentifierRef<'a>,
     pub fields: Vec<FieldRef<'a>>,
 }
 
-impl<'a> Parser<'a> for UnionRef<'a> {
-    fn parse(input: &'a str) -> IResult<&'a str, Self> {
+impl<'a> UnionRef<'a> {
+    /// Parses starting at `input`, computing `span` as an absolute offset
+    /// into `origin` (the whole document `input` is a suffix of).
+    pub(crate) fn parse_in(input: &'a str, origin: &'a str) -> IResult<&'a str, Self> {
         map(
-            tuple((
+            consumed(tuple((
                 opt(terminated(
                     CommentRef::parse,
                     terminated(Linefeed::parse, opt(Space::parse)),
@@ -324,8 +424,9 @@ impl<'a> Parser<'a> for UnionRef<'a> {
                 delimited(opt(Separator::parse), cchar('{'), opt(Separator::parse)),
                 separated_list0(Separator::parse, FieldRef::parse),
                 pair(opt(Separator::parse), cchar('}')),
-            )),
-            |(doc_comment, _, name, _, fields, _)| Self {
+            ))),
+            |(consumed, (doc_comment, _, name, _, fields, _))| Self {
+                span: span_of(origin, consumed),
                 doc_comment,
                 name,
                 fields,
@@ -334,8 +435,15 @@ impl<'a> Parser<'a> for UnionRef<'a> {
     }
 }
 
+impl<'a> Parser<'a> for UnionRef<'a> {
+    fn parse(input: &'a str) -> IResult<&'a str, Self> {
+        Self::parse_in(input, input)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Union {
+    pub span: Span,
     pub doc_comment: Option<Comment>,
     pub name: Identifier,
     pub fields: Vec<Field>,
@@ -344,6 +452,7 @@ pub struct Union {
 impl<'a> From<UnionRef<'a>> for Union {
     fn from(r: UnionRef<'a>) -> Self {
         Self {
+            span: r.span,
             doc_comment: match r.doc_comment {
                 Some(d) => Some(d.into()),
                 None => None,
@@ -363,15 +472,18 @@ impl<'a> Parser<'a> for Union {
 // Exception       ::=  'exception' Identifier '{' Field* '}'
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExceptionRef<'a> {
+    pub span: Span,
     pub doc_comment: Option<CommentRef<'a>>,
     pub name: IdentifierRef<'a>,
     pub fields: Vec<FieldRef<'a>>,
 }
 
-impl<'a> Parser<'a> for ExceptionRef<'a> {
-    fn parse(input: &'a str) -> IResult<&'a str, Self> {
+impl<'a> ExceptionRef<'a> {
+    /// Parses starting at `input`, computing `span` as an absolute offset
+    /// into `origin` (the whole document `input` is a suffix of).
+    pub(crate) fn parse_in(input: &'a str, origin: &'a str) -> IResult<&'a str, Self> {
         map(
-            tuple((
+            consumed(tuple((
                 opt(terminated(
                     CommentRef::parse,
                     terminated(Linefeed::parse, opt(Space::parse)),
@@ -381,8 +493,9 @@ impl<'a> Parser<'a> for ExceptionRef<'a> {
                 delimited(opt(Separator::parse), cchar('{'), opt(Separator::parse)),
                 separated_list0(Separator::parse, FieldRef::parse),
                 pair(opt(Separator::parse), cchar('}')),
-            )),
-            |(doc_comment, _, name, _, fields, _)| Self {
+            ))),
+            |(consumed, (doc_comment, _, name, _, fields, _))| Self {
+                span: span_of(origin, consumed),
                 doc_comment,
                 name,
                 fields,
@@ -391,8 +504,15 @@ impl<'a> Parser<'a> for ExceptionRef<'a> {
     }
 }
 
+impl<'a> Parser<'a> for ExceptionRef<'a> {
+    fn parse(input: &'a str) -> IResult<&'a str, Self> {
+        Self::parse_in(input, input)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Exception {
+    pub span: Span,
     pub doc_comment: Option<Comment>,
     pub name: Identifier,
     pub fields: Vec<Field>,
@@ -401,6 +521,7 @@ pub struct Exception {
 impl<'a> From<ExceptionRef<'a>> for Exception {
     fn from(r: ExceptionRef<'a>) -> Self {
         Self {
+            span: r.span,
             doc_comment: match r.doc_comment {
                 Some(d) => Some(d.into()),
                 None => None,
@@ -420,16 +541,19 @@ impl<'a> Parser<'a> for Exception {
 // Service         ::=  'service' Identifier ( 'extends' Identifier )? '{' Function* '}'
 #[derive(Debug, Clone, PartialEq)]
 pub struct ServiceRef<'a> {
+    pub span: Span,
     pub doc_comment: Option<CommentRef<'a>>,
     pub name: IdentifierRef<'a>,
     pub extension: Option<IdentifierRef<'a>>,
     pub functions: Vec<FunctionRef<'a>>,
 }
 
-impl<'a> Parser<'a> for ServiceRef<'a> {
-    fn parse(input: &'a str) -> IResult<&'a str, Self> {
+impl<'a> ServiceRef<'a> {
+    /// Parses starting at `input`, computing `span` as an absolute offset
+    /// into `origin` (the whole document `input` is a suffix of).
+    pub(crate) fn parse_in(input: &'a str, origin: &'a str) -> IResult<&'a str, Self> {
         map(
-            tuple((
+            consumed(tuple((
                 opt(terminated(
                     CommentRef::parse,
                     terminated(Linefeed::parse, opt(Space::parse)),
@@ -453,8 +577,9 @@ impl<'a> Parser<'a> for ServiceRef<'a> {
                     separated_list0(Separator::parse, FunctionRef::parse),
                     pair(opt(Separator::parse), cchar('}')),
                 ),
-            )),
-            |(doc_comment, name, extension, functions)| Self {
+            ))),
+            |(consumed, (doc_comment, name, extension, functions))| Self {
+                span: span_of(origin, consumed),
                 doc_comment,
                 name,
                 extension,
@@ -464,8 +589,15 @@ impl<'a> Parser<'a> for ServiceRef<'a> {
     }
 }
 
+impl<'a> Parser<'a> for ServiceRef<'a> {
+    fn parse(input: &'a str) -> IResult<&'a str, Self> {
+        Self::parse_in(input, input)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Service {
+    pub span: Span,
     pub doc_comment: Option<Comment>,
     pub name: Identifier,
     pub extension: Option<Identifier>,
@@ -475,6 +607,7 @@ pub struct Service {
 impl<'a> From<ServiceRef<'a>> for Service {
     fn from(r: ServiceRef<'a>) -> Self {
         Self {
+            span: r.span,
             doc_comment: match r.doc_comment {
                 Some(d) => Some(d.into()),
                 None => None,
@@ -498,13 +631,48 @@ mod test {
 
     use super::*;
 
+    const ZERO_SPAN: Span = Span { start: 0, end: 0 };
+
+    /// Clears `span`/`location` so two parses of differently-formatted
+    /// equivalent sources can be compared on content alone.
+    fn strip_enum_span(e: EnumRef) -> EnumRef {
+        EnumRef {
+            span: ZERO_SPAN,
+            doc_comment: e.doc_comment,
+            name: e.name,
+            children: e
+                .children
+                .into_iter()
+                .map(|c| EnumValueRef {
+                    location: ZERO_SPAN,
+                    name: c.name,
+                    value: c.value,
+                })
+                .collect(),
+        }
+    }
+
+    /// Clears `span` so two parses of differently-formatted equivalent
+    /// sources can be compared on content alone.
+    fn strip_struct_span(s: StructRef) -> StructRef {
+        StructRef {
+            span: ZERO_SPAN,
+            doc_comment: s.doc_comment,
+            name: s.name,
+            fields: s.fields,
+        }
+    }
+
     #[test]
     fn test_const() {
+        let source = "const bool is_rust_easy = 'yes!';";
         assert_eq!(
-            ConstRef::parse("const bool is_rust_easy = 'yes!';")
-                .unwrap()
-                .1,
+            ConstRef::parse(source).unwrap().1,
             ConstRef {
+                span: Span {
+                    start: 0,
+                    end: source.len(),
+                },
                 doc_comment: None,
                 name: IdentifierRef::from("is_rust_easy"),
                 type_: FieldTypeRef::Bool,
@@ -515,9 +683,14 @@ mod test {
 
     #[test]
     fn test_typedef() {
+        let source = "typedef i32 MyI32";
         assert_eq!(
-            TypedefRef::parse("typedef i32 MyI32").unwrap().1,
+            TypedefRef::parse(source).unwrap().1,
             TypedefRef {
+                span: Span {
+                    start: 0,
+                    end: source.len(),
+                },
                 doc_comment: None,
                 old: FieldTypeRef::I32,
                 alias: IdentifierRef::from("MyI32")
@@ -527,37 +700,51 @@ mod test {
 
     #[test]
     fn test_enum() {
+        let source = "enum PL { Rust Go=2 , Cpp = 3 }";
         let expected = EnumRef {
+            span: Span {
+                start: 0,
+                end: source.len(),
+            },
             doc_comment: None,
             name: IdentifierRef::from("PL"),
             children: vec![
                 EnumValueRef {
+                    location: Location { start: 10, end: 14 },
                     name: IdentifierRef::from("Rust"),
                     value: None,
                 },
                 EnumValueRef {
+                    location: Location { start: 15, end: 19 },
                     name: IdentifierRef::from("Go"),
                     value: Some(IntConstant::from(2)),
                 },
                 EnumValueRef {
+                    location: Location { start: 22, end: 29 },
                     name: IdentifierRef::from("Cpp"),
                     value: Some(IntConstant::from(3)),
                 },
             ],
         };
+        assert_eq!(EnumRef::parse(source).unwrap().1, expected);
+
+        // Same enum, written compactly: separator handling differs but the
+        // parsed content (ignoring spans, which naturally differ) must match.
+        let compact_source = "enum PL{Rust Go=2,Cpp=3}";
         assert_eq!(
-            EnumRef::parse("enum PL { Rust Go=2 , Cpp = 3 }").unwrap().1,
-            expected
-        );
-        assert_eq!(
-            EnumRef::parse("enum PL{Rust Go=2,Cpp=3}").unwrap().1,
-            expected
+            strip_enum_span(EnumRef::parse(compact_source).unwrap().1),
+            strip_enum_span(expected)
         );
     }
 
     #[test]
     fn test_struct() {
+        let source = "struct user { 1 : optional string name ; 2 : i32 age = 18 }";
         let expected = StructRef {
+            span: Span {
+                start: 0,
+                end: source.len(),
+            },
             doc_comment: None,
             name: IdentifierRef::from("user"),
             fields: vec![
@@ -577,22 +764,22 @@ mod test {
                 },
             ],
         };
+        assert_eq!(StructRef::parse(source).unwrap().1, expected);
+
+        // Same struct, written compactly: separator handling differs but the
+        // parsed content (ignoring spans, which naturally differ) must match.
+        let compact_source = "struct user{1:optional string name; 2:i32 age=18}";
         assert_eq!(
-            StructRef::parse("struct user{1:optional string name; 2:i32 age=18}")
-                .unwrap()
-                .1,
-            expected
-        );
-        assert_eq!(
-            StructRef::parse("struct user { 1 : optional string name ; 2 : i32 age = 18 }")
-                .unwrap()
-                .1,
-            expected
+            strip_struct_span(StructRef::parse(compact_source).unwrap().1),
+            strip_struct_span(expected)
         );
     }
 
     #[test]
     fn test_service() {
+        let source = "service DemoService extends BaseService { \
+         string GetUser(required string name),
+         string GetUser(required string name) }";
         let function = FunctionRef {
             oneway: false,
             returns: Some(FieldTypeRef::String),
@@ -607,20 +794,36 @@ mod test {
             exceptions: None,
         };
         let expected = ServiceRef {
+            span: Span {
+                start: 0,
+                end: source.len(),
+            },
             doc_comment: None,
             name: IdentifierRef::from("DemoService"),
             extension: Some(IdentifierRef::from("BaseService")),
             functions: vec![function.clone(), function],
         };
+        assert_eq!(ServiceRef::parse(source).unwrap().1, expected);
+    }
+
+    #[test]
+    fn test_parse_in_computes_absolute_spans_across_definitions() {
+        let source = "typedef i32 MyI32\ntypedef i64 MyI64";
+        let (rest, first) = TypedefRef::parse_in(source, source).unwrap();
         assert_eq!(
-            ServiceRef::parse(
-                "service DemoService extends BaseService { \
-         string GetUser(required string name),
-         string GetUser(required string name) }"
-            )
-            .unwrap()
-            .1,
-            expected
+            first.span,
+            Span {
+                start: 0,
+                end: "typedef i32 MyI32".len(),
+            }
+        );
+
+        let rest = rest.trim_start();
+        let (_, second) = TypedefRef::parse_in(rest, source).unwrap();
+        assert!(second.span.start > 0);
+        assert_eq!(
+            &source[second.span.start..second.span.end],
+            "typedef i64 MyI64"
         );
     }
 }